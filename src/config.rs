@@ -0,0 +1,129 @@
+//! Non-volatile LoRa configuration, stored in a dedicated flash sector.
+//!
+//! The record is `postcard`-encoded with a magic header, format
+//! version, and checksum so a blank (erased, all-`0xFF`) or corrupted
+//! sector falls back to [`Config::default()`] rather than booting with
+//! garbage on-air settings.
+
+use crate::flash::FlashWriter;
+use crate::protocol::CommandKind;
+use serde::{Deserialize, Serialize};
+use stm32f4xx_hal::pac::FLASH;
+
+const MAGIC: u32 = 0x4C52_4143; // "LRAC"
+// Bump whenever `Config`'s layout changes so a device upgrading from an
+// older image falls back to `Config::default()` instead of decoding a
+// flash record with a different field set into the new shape.
+const VERSION: u8 = 2;
+
+/// Sector 7 (128KiB) on the STM32F401/411 flash map — these parts have
+/// 512KiB of flash in sectors 0-7, so sector 7 is the last one, well
+/// away from the firmware image.
+pub const CONFIG_SECTOR: u8 = 7;
+pub const CONFIG_ADDRESS: u32 = 0x0806_0000;
+
+/// Size of the region reserved for the encoded record.
+pub const CONFIG_SIZE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub address: u16,
+    pub network_id: u8,
+    pub spreading_factor: u8,
+    pub bandwidth: u8,
+    pub coding_rate: u8,
+    pub beacon_interval_s: u32,
+    /// TX power in dBm, tunable live from the encoder menu.
+    pub tx_power: u8,
+    /// CW sidetone speed in words per minute, tunable live from the
+    /// encoder menu; see [`crate::cw::dot_millis`].
+    pub cw_wpm: u8,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            network_id: 18,
+            spreading_factor: 9,
+            bandwidth: 7,
+            coding_rate: 1,
+            beacon_interval_s: 30,
+            tx_power: 20,
+            cw_wpm: 20,
+        }
+    }
+}
+
+impl Config {
+    /// Applies a received [`CommandKind`], returning `true` if anything
+    /// changed (and is therefore worth persisting).
+    pub fn apply(&mut self, cmd: &CommandKind) -> bool {
+        let next = *self;
+        let mut updated = next;
+        match *cmd {
+            CommandKind::SetAddress(addr) => updated.address = addr,
+            CommandKind::SetNetworkId(id) => updated.network_id = id,
+            CommandKind::SetBeaconInterval(secs) => updated.beacon_interval_s = secs as u32,
+        }
+        let changed = updated != next;
+        *self = updated;
+        changed
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    magic: u32,
+    version: u8,
+    config: Config,
+    checksum: u32,
+}
+
+fn checksum(config: &Config) -> u32 {
+    // Additive checksum: enough to catch a blank/torn page, not meant
+    // to be cryptographic.
+    let mut sum: u32 = 0;
+    sum = sum.wrapping_add(config.address as u32);
+    sum = sum.wrapping_add(config.network_id as u32);
+    sum = sum.wrapping_add(config.spreading_factor as u32);
+    sum = sum.wrapping_add(config.bandwidth as u32);
+    sum = sum.wrapping_add(config.coding_rate as u32);
+    sum = sum.wrapping_add(config.beacon_interval_s);
+    sum = sum.wrapping_add(config.tx_power as u32);
+    sum = sum.wrapping_add(config.cw_wpm as u32);
+    sum ^ MAGIC
+}
+
+/// Decodes a `Config` from a raw flash page, falling back to defaults
+/// if the page is blank, corrupt, or from an unknown format version.
+pub fn decode(page: &[u8]) -> Config {
+    match postcard::from_bytes::<Record>(page) {
+        Ok(record)
+            if record.magic == MAGIC
+                && record.version == VERSION
+                && record.checksum == checksum(&record.config) =>
+        {
+            record.config
+        }
+        _ => Config::default(),
+    }
+}
+
+/// Erases the config sector and programs `config` into it.
+pub fn persist(flash: &FLASH, config: &Config) {
+    let record = Record {
+        magic: MAGIC,
+        version: VERSION,
+        config: *config,
+        checksum: checksum(config),
+    };
+
+    let Ok(encoded) = postcard::to_vec::<_, CONFIG_SIZE>(&record) else {
+        return;
+    };
+
+    let writer = FlashWriter::new(flash);
+    writer.erase_sector(CONFIG_SECTOR);
+    writer.program(CONFIG_ADDRESS, encoded.as_slice());
+}
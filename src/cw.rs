@@ -0,0 +1,108 @@
+//! Morse (CW) encoding for the audible sidetone readout.
+//!
+//! Converts ASCII text into a flat sequence of [`Element`]s (tone-on
+//! marks and tone-off spaces, each a multiple of one dot-unit) that the
+//! `cw_tick` task in `main.rs` can play back by gating a PWM sidetone
+//! channel on a fixed-rate timer, without ever blocking on the caller.
+
+use heapless::Vec;
+
+/// A keyed interval: `Mark` gates the sidetone on for its duration,
+/// `Space` gates it off. Both carry their length in dot-units so the
+/// player doesn't need a separate timing table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    Mark(u8),
+    Space(u8),
+}
+
+const DOT: u8 = 1;
+const DASH: u8 = 3;
+const ELEMENT_GAP: u8 = 1;
+const LETTER_GAP: u8 = 3;
+const WORD_GAP: u8 = 7;
+
+/// Capacity for one played message. Worst case is `MAX_FRAME` (64)
+/// digits back-to-back: each digit is the longest Morse code (5
+/// symbols = 5 marks + 4 element-gaps) plus one letter-gap, i.e. 10
+/// elements/char, for 640 elements total.
+pub const MAX_ELEMENTS: usize = 640;
+
+/// International Morse code for letters and digits; anything else
+/// (punctuation, control bytes) is silently dropped by [`encode`].
+fn code(c: char) -> Option<&'static str> {
+    Some(match c.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        _ => return None,
+    })
+}
+
+/// Appends the Morse elements for `text` to `out`. Words (split on
+/// whitespace) are separated by a 7-unit gap, letters within a word by
+/// a 3-unit gap, and the dots/dashes of one letter by a 1-unit gap.
+/// Characters with no Morse representation are skipped rather than
+/// breaking the stream.
+pub fn encode(text: &str, out: &mut Vec<Element, MAX_ELEMENTS>) {
+    let mut at_message_start = true;
+    for word in text.split_whitespace() {
+        if !at_message_start {
+            let _ = out.push(Element::Space(WORD_GAP));
+        }
+        let mut at_word_start = true;
+        for pattern in word.chars().filter_map(code) {
+            if !at_word_start {
+                let _ = out.push(Element::Space(LETTER_GAP));
+            }
+            at_word_start = false;
+            at_message_start = false;
+            for (i, symbol) in pattern.chars().enumerate() {
+                if i > 0 {
+                    let _ = out.push(Element::Space(ELEMENT_GAP));
+                }
+                let _ = out.push(Element::Mark(if symbol == '-' { DASH } else { DOT }));
+            }
+        }
+    }
+}
+
+/// PARIS-standard dot duration in milliseconds for `wpm` words per
+/// minute (`1200 / wpm`); `wpm` is clamped to at least 1 to avoid a
+/// division by zero from a corrupt or not-yet-initialized config.
+pub fn dot_millis(wpm: u8) -> u32 {
+    1200 / wpm.max(1) as u32
+}
@@ -0,0 +1,75 @@
+//! Minimal erase/program helper for the STM32F4 flash controller, used
+//! to persist [`crate::config::Config`] across reboots.
+
+use stm32f4xx_hal::pac::FLASH;
+
+const UNLOCK_KEY1: u32 = 0x4567_0123;
+const UNLOCK_KEY2: u32 = 0xCDEF_89AB;
+
+/// 32-bit program size (`PSIZE` field), matching the word-at-a-time
+/// writes [`FlashWriter::program`] performs.
+const PSIZE_X32: u8 = 0b10;
+
+pub struct FlashWriter<'a> {
+    flash: &'a FLASH,
+}
+
+impl<'a> FlashWriter<'a> {
+    pub fn new(flash: &'a FLASH) -> Self {
+        Self { flash }
+    }
+
+    fn unlock(&self) {
+        if self.flash.cr.read().lock().bit_is_set() {
+            self.flash.keyr.write(|w| unsafe { w.bits(UNLOCK_KEY1) });
+            self.flash.keyr.write(|w| unsafe { w.bits(UNLOCK_KEY2) });
+        }
+    }
+
+    fn lock(&self) {
+        self.flash.cr.modify(|_, w| w.lock().set_bit());
+    }
+
+    fn wait_busy(&self) {
+        while self.flash.sr.read().bsy().bit_is_set() {}
+    }
+
+    /// Erases `sector` (required before programming it). A blank sector
+    /// reads back as all-`0xFF`, which [`crate::config::decode`] treats
+    /// as "no saved config".
+    pub fn erase_sector(&self, sector: u8) {
+        self.unlock();
+        self.wait_busy();
+        self.flash
+            .cr
+            .modify(|_, w| unsafe { w.ser().set_bit().snb().bits(sector) });
+        self.flash.cr.modify(|_, w| w.strt().set_bit());
+        self.wait_busy();
+        self.flash.cr.modify(|_, w| w.ser().clear_bit());
+        self.lock();
+    }
+
+    /// Programs `data` starting at `address`. The target sector must
+    /// already be erased.
+    pub fn program(&self, address: u32, data: &[u8]) {
+        self.unlock();
+        self.flash
+            .cr
+            .modify(|_, w| unsafe { w.pg().set_bit().psize().bits(PSIZE_X32) });
+
+        for (i, word) in data.chunks(4).enumerate() {
+            let mut bytes = [0xFFu8; 4];
+            bytes[..word.len()].copy_from_slice(word);
+            let value = u32::from_le_bytes(bytes);
+            let ptr = (address + (i as u32) * 4) as *mut u32;
+            // Safety: `address` points into the dedicated config sector,
+            // which was just erased, and the controller serializes each
+            // word write via `wait_busy` before the next one starts.
+            unsafe { core::ptr::write_volatile(ptr, value) };
+            self.wait_busy();
+        }
+
+        self.flash.cr.modify(|_, w| w.pg().clear_bit());
+        self.lock();
+    }
+}
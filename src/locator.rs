@@ -0,0 +1,44 @@
+//! Maidenhead grid locator conversion.
+
+/// Computes the 6-character Maidenhead locator for a decimal lat/lon
+/// position, e.g. `JO62qn`.
+pub fn maidenhead(lat: f32, lon: f32) -> heapless::String<6> {
+    let lon = lon + 180.0; // 0..360
+    let lat = lat + 90.0; // 0..180
+
+    let field_lon = (lon / 20.0) as u8;
+    let field_lat = (lat / 10.0) as u8;
+    let square_lon = ((lon % 20.0) / 2.0) as u8;
+    let square_lat = (lat % 10.0) as u8;
+    let sub_lon = (((lon % 2.0) * 24.0) / 2.0) as u8;
+    let sub_lat = (((lat % 1.0) * 24.0) / 1.0) as u8;
+
+    let mut locator: heapless::String<6> = heapless::String::new();
+    let _ = locator.push((b'A' + field_lon) as char);
+    let _ = locator.push((b'A' + field_lat) as char);
+    let _ = locator.push((b'0' + square_lon) as char);
+    let _ = locator.push((b'0' + square_lat) as char);
+    let _ = locator.push((b'a' + sub_lon) as char);
+    let _ = locator.push((b'a' + sub_lat) as char);
+    locator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_island_is_jj00aa() {
+        assert_eq!(maidenhead(0.0, 0.0).as_str(), "JJ00aa");
+    }
+
+    #[test]
+    fn whole_degree_position() {
+        assert_eq!(maidenhead(20.0, -40.0).as_str(), "HL00aa");
+    }
+
+    #[test]
+    fn fractional_position() {
+        assert_eq!(maidenhead(10.5, 15.5).as_str(), "JK70sm");
+    }
+}
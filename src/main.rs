@@ -4,27 +4,92 @@
 use panic_probe as _;
 use defmt_rtt as _;
 
-#[rtic::app(device = stm32f4xx_hal::pac, peripherals = true)]
+mod config;
+mod cw;
+mod flash;
+mod locator;
+mod menu;
+mod nmea;
+mod protocol;
+mod rylr;
+mod sdlog;
+mod signal;
+
+#[rtic::app(device = stm32f4xx_hal::pac, peripherals = true, dispatchers = [EXTI0, EXTI1])]
 mod app {
     use stm32f4xx_hal::{
         prelude::*,
         gpio::{Output, Pin},
         pac,
-        timer::{CounterHz, Event, Timer, Flag},
-        serial::{Serial, Config, Event as SerialEvent},
+        timer::{CounterHz, Event, Timer, Flag, pwm::PwmChannel},
+        serial::{Serial, Config, Event as SerialEvent, Tx, Rx},
         i2c::I2c,
+        spi::{Mode, Phase, Polarity, Spi},
+        qei::Qei,
+        gpio::{Edge, Input},
     };
+    use embedded_sdmmc::SdCard;
     use ssd1306::{prelude::*, Ssd1306, I2CDisplayInterface, mode::BufferedGraphicsMode};
     use display_interface_i2c::I2CInterface;
     use embedded_graphics::{
         mono_font::{ascii::FONT_6X10, MonoTextStyleBuilder},
         pixelcolor::BinaryColor,
         prelude::*,
+        primitives::{PrimitiveStyle, Rectangle},
         text::Text,
     };
-    use heapless::Vec;
+    use heapless::{spsc::{Consumer, Producer, Queue}, Vec};
     use defmt::info;
 
+    use crate::config::{self, Config};
+    use crate::cw::{self, Element, MAX_ELEMENTS};
+    use crate::locator;
+    use crate::menu::{Field, Menu};
+    use crate::nmea::{self, GpsFix};
+    use crate::protocol::{self, CommandKind, Message, MAX_FRAME, MAX_PAYLOAD};
+    use crate::rylr;
+    use crate::sdlog::{LogRecord, SdLogger};
+    use crate::signal::{self, RssiFilter};
+
+    /// SPI2 SCK/MISO/MOSI + delay, matching `SdLogger`'s `SdCard` type.
+    type SdSpi = Spi<pac::SPI2>;
+    type SdDelay = stm32f4xx_hal::timer::SysDelay;
+    type SdCs = Pin<'B', 12, Output>;
+
+    /// TX/RX halves of the LoRa module's UART, split in `init` so the
+    /// RX half can be owned exclusively by `uart4_handler` — see that
+    /// task's doc comment for why.
+    type LoraTx = Tx<pac::UART4>;
+    type LoraRx = Rx<pac::UART4>;
+
+    /// Log-record queue capacity between the `process_frame` producer
+    /// and the `sd_log` consumer task.
+    const LOG_QUEUE_CAPACITY: usize = 9;
+
+    /// Quadrature (x4) counts per detent on the rotary encoder used.
+    const COUNTS_PER_DETENT: i16 = 4;
+
+    type Encoder = Qei<pac::TIM3>;
+    type ButtonPin = Pin<'C', 13, Input>;
+    type CwPwm = PwmChannel<pac::TIM1, 0>;
+
+    /// `cw_tick`'s fixed sample rate: 5ms resolution is coarse next to a
+    /// dot length at high WPM, but comfortably resolves the 5-40 WPM
+    /// range the menu allows.
+    const CW_TICK_HZ: u32 = 200;
+    const CW_TICK_MS: u32 = 1000 / CW_TICK_HZ;
+
+    /// Sidetone pitch, within the requested 700-1000Hz band.
+    const CW_TONE_HZ: u32 = 800;
+
+    /// Morse element queue capacity between `cw_play` and `cw_tick`;
+    /// one extra slot is reserved by `heapless::spsc`, as with the other
+    /// queues in this file.
+    const CW_QUEUE_CAPACITY: usize = MAX_ELEMENTS + 1;
+
+    /// Broadcast address the locator beacon is sent to.
+    const BEACON_ADDRESS: u16 = 0;
+
     // Correct Type Alias for ssd1306 v0.9.0
     type LoraDisplay = Ssd1306<
         I2CInterface<I2c<pac::I2C1>>, 
@@ -34,18 +99,42 @@ mod app {
 
     #[shared]
     struct Shared {
-        lora_uart: Serial<pac::UART4>,
+        lora_tx: LoraTx,
         display: LoraDisplay,
-        rx_buffer: Vec<u8, 32>,
+        gps_fix: Option<GpsFix>,
+        menu: Menu,
+        config: Config,
     }
 
     #[local]
     struct Local {
         led: Pin<'A', 5, Output>,
         timer: CounterHz<pac::TIM2>,
+        lora_rx: LoraRx,
+        gps_uart: Serial<pac::USART2>,
+        gps_line: Vec<u8, 96>,
+        beacon_seq: u16,
+        beacon_tick: u32,
+        flash: pac::FLASH,
+        log_tx: Producer<'static, LogRecord, LOG_QUEUE_CAPACITY>,
+        log_rx: Consumer<'static, LogRecord, LOG_QUEUE_CAPACITY>,
+        log_seq: u32,
+        sd_logger: SdLogger<SdSpi, SdCs, SdDelay>,
+        encoder: Encoder,
+        encoder_count: u16,
+        button: ButtonPin,
+        menu_timer: CounterHz<pac::TIM4>,
+        cw_tx: Producer<'static, Element, CW_QUEUE_CAPACITY>,
+        cw_rx: Consumer<'static, Element, CW_QUEUE_CAPACITY>,
+        cw_timer: CounterHz<pac::TIM5>,
+        cw_pwm: CwPwm,
+        cw_remaining_ticks: u32,
     }
 
-    #[init]
+    #[init(local = [
+        log_queue: Queue<LogRecord, LOG_QUEUE_CAPACITY> = Queue::new(),
+        cw_queue: Queue<Element, CW_QUEUE_CAPACITY> = Queue::new(),
+    ])]
     fn init(cx: init::Context) -> (Shared, Local) {
         let dp = cx.device;
         let rcc = dp.RCC.constrain();
@@ -66,6 +155,57 @@ mod app {
             &clocks,
         ).unwrap();
         lora_uart.listen(SerialEvent::RxNotEmpty);
+        // Split into independent halves so `lora_rx` can be owned
+        // solely by `uart4_handler` (see its doc comment): `lora_tx`
+        // is the only half ever locked by other tasks, so the RX ISR
+        // is never masked by their blocking AT-command writes.
+        let (mut lora_tx, lora_rx) = lora_uart.split();
+
+        // --- Load persisted LoRa config and program the module ---
+        // Safety: reads `CONFIG_SIZE` bytes from the dedicated config
+        // sector, which is memory-mapped and readable without unlocking
+        // the flash controller.
+        let config_page = unsafe {
+            core::slice::from_raw_parts(config::CONFIG_ADDRESS as *const u8, config::CONFIG_SIZE)
+        };
+        let loaded_config = config::decode(config_page);
+        apply_config_at(&mut lora_tx, &loaded_config);
+
+        // --- GPS UART (PA2=TX, PA3=RX) ---
+        let gps_tx = gpioa.pa2.into_alternate();
+        let gps_rx = gpioa.pa3.into_alternate();
+        let mut gps_uart = Serial::new(
+            dp.USART2, (gps_tx, gps_rx),
+            Config::default().baudrate(9600_u32.bps()),
+            &clocks,
+        ).unwrap();
+        gps_uart.listen(SerialEvent::RxNotEmpty);
+
+        // --- SD card SPI (PB13=SCK, PB14=MISO, PB15=MOSI, PB12=CS) ---
+        let sck = gpiob.pb13.into_alternate();
+        let miso = gpiob.pb14.into_alternate();
+        let mosi = gpiob.pb15.into_alternate();
+        let sd_cs = gpiob.pb12.into_push_pull_output();
+        let sd_mode = Mode { polarity: Polarity::IdleLow, phase: Phase::CaptureOnFirstTransition };
+        let sd_spi = Spi::new(dp.SPI2, (sck, miso, mosi), sd_mode, 400.kHz(), &clocks);
+        let sd_delay = cx.core.SYST.delay(&clocks);
+        let sd_card = SdCard::new(sd_spi, sd_cs, sd_delay);
+        let mut sd_logger = SdLogger::new(sd_card);
+        let sd_present = sd_logger.mount();
+        info!("SD card present: {}", sd_present);
+
+        // --- Rotary encoder (PA6=CH1, PA7=CH2, TIM3 in QEI mode) ---
+        let encoder = Timer::new(dp.TIM3, &clocks).qei((
+            gpioa.pa6.into_alternate(),
+            gpioa.pa7.into_alternate(),
+        ));
+
+        // --- Menu button (PC13, active low) ---
+        let mut syscfg = dp.SYSCFG.constrain();
+        let mut button = gpioc.pc13.into_pull_up_input();
+        button.make_interrupt_source(&mut syscfg);
+        button.trigger_on_edge(&mut dp.EXTI, Edge::Falling);
+        button.enable_interrupt(&mut dp.EXTI);
 
         // --- OLED I2C (PB8=SCL, PB9=SDA) ---
         let scl = gpiob.pb8.into_alternate_open_drain();
@@ -78,66 +218,552 @@ mod app {
 
         // --- Timer (1Hz) ---
         let mut timer = Timer::new(dp.TIM2, &clocks).counter_hz();
-        timer.start(1_u32.Hz()).unwrap(); 
+        timer.start(1_u32.Hz()).unwrap();
         timer.listen(Event::Update);
 
+        // --- Encoder poll timer (100Hz) ---
+        // The QEI peripheral only counts quadrature pulses; it doesn't
+        // raise its own interrupt on every edge, so a separate periodic
+        // timer drives `encoder_poll` to sample the count register.
+        let mut menu_timer = Timer::new(dp.TIM4, &clocks).counter_hz();
+        menu_timer.start(100_u32.Hz()).unwrap();
+        menu_timer.listen(Event::Update);
+
+        // --- CW sidetone (PA8 = TIM1_CH1 PWM, buzzer) ---
+        let cw_pin = gpioa.pa8.into_alternate();
+        let mut cw_pwm = Timer::new(dp.TIM1, &clocks).pwm_hz(cw_pin, CW_TONE_HZ.Hz(), &clocks);
+        cw_pwm.set_duty(0);
+        cw_pwm.enable();
+
+        // --- CW keyer tick (fixed rate; see `CW_TICK_HZ`) ---
+        let mut cw_timer = Timer::new(dp.TIM5, &clocks).counter_hz();
+        cw_timer.start(CW_TICK_HZ.Hz()).unwrap();
+        cw_timer.listen(Event::Update);
+
         info!("System Live. Verified Mouth-to-Ear Wiring.");
 
-        (Shared { 
-            lora_uart, 
-            display, 
-            rx_buffer: Vec::new() 
-        }, Local { led, timer })
+        let (log_tx, log_rx) = cx.local.log_queue.split();
+        let (cw_tx, cw_rx) = cx.local.cw_queue.split();
+
+        (
+            Shared { lora_tx, display, gps_fix: None, menu: Menu::new(), config: loaded_config },
+            Local {
+                led,
+                timer,
+                lora_rx,
+                gps_uart,
+                gps_line: Vec::new(),
+                beacon_seq: 0,
+                beacon_tick: 0,
+                flash: dp.FLASH,
+                log_tx,
+                log_rx,
+                log_seq: 0,
+                sd_logger,
+                encoder,
+                encoder_count: 0,
+                button,
+                menu_timer,
+                cw_tx,
+                cw_rx,
+                cw_timer,
+                cw_pwm,
+                cw_remaining_ticks: 0,
+            },
+        )
     }
 
-    #[task(binds = UART4, shared = [lora_uart, display, rx_buffer])]
+    /// Programs the RYLR module's address/network/RF parameters over
+    /// AT commands to match `config`.
+    fn apply_config_at(uart: &mut LoraTx, config: &Config) {
+        let mut cmd: heapless::String<48> = heapless::String::new();
+        let _ = core::fmt::write(&mut cmd, format_args!("AT+ADDRESS={}\r\n", config.address));
+        for b in cmd.as_bytes() {
+            let _ = nb::block!(uart.write(*b));
+        }
+
+        cmd.clear();
+        let _ = core::fmt::write(&mut cmd, format_args!("AT+NETWORKID={}\r\n", config.network_id));
+        for b in cmd.as_bytes() {
+            let _ = nb::block!(uart.write(*b));
+        }
+
+        cmd.clear();
+        let _ = core::fmt::write(
+            &mut cmd,
+            format_args!(
+                "AT+PARAMETER={},{},{},4\r\n",
+                config.spreading_factor, config.bandwidth, config.coding_rate
+            ),
+        );
+        for b in cmd.as_bytes() {
+            let _ = nb::block!(uart.write(*b));
+        }
+
+        cmd.clear();
+        let _ = core::fmt::write(&mut cmd, format_args!("AT+CRFOP={}\r\n", config.tx_power));
+        for b in cmd.as_bytes() {
+            let _ = nb::block!(uart.write(*b));
+        }
+    }
+
+    /// Minimal-latency ISR: read the byte, accumulate it into a local
+    /// line buffer, and hand the complete line to `process_frame` once
+    /// it's terminated. No locking of `display`, no parsing, nothing
+    /// that can stall the UART at 115200 baud.
+    ///
+    /// `line_buf` is accumulated entirely locally, not byte-by-byte
+    /// through a shared producer/consumer queue: if a line overruns
+    /// `MAX_FRAME`, `overflowed` just marks it to be discarded whole
+    /// once `\n` arrives, instead of desyncing a shared queue with a
+    /// partially-written line that nothing will ever drain evenly
+    /// against (the old push-one-byte-at-a-time version had no way to
+    /// retract the bytes it had already pushed on overflow, so the next
+    /// line's `process_frame` call would dequeue its predecessor's
+    /// leftovers). Handing `process_frame` the complete line as its
+    /// spawn argument also lets RTIC's own task queue do the buffering
+    /// between the ISR and the task — no hand-rolled queue needed.
+    ///
+    /// `lora_rx` is `local`, not `shared` — it's `init`'s RX half of the
+    /// split `UART4` serial port, and no other task ever touches it. So
+    /// unlike a single undivided `Serial`, this ISR can never be masked
+    /// by another task's lock: the priority-ceiling protocol only raises
+    /// a task's effective priority while holding a lock on a resource
+    /// *this* task also shares, and `lora_rx` has no other sharers.
+    /// `lora_tx` (the half `handle_command`/`tim2_handler` block on for
+    /// AT-command writes) is a separate resource with its own, lower
+    /// ceiling, so those blocking writes can't delay this ISR at all.
+    /// The priority-3 bump above `process_frame` (priority 2) is kept as
+    /// defense in depth even though it's no longer load-bearing for
+    /// this specifically.
+    ///
+    /// The RYLR module reports received packets as AT text
+    /// (`+RCV=<addr>,<len>,<data>,<rssi>,<snr>`), so lines are
+    /// `\n`-terminated rather than COBS `0x00`-delimited; `<data>` is
+    /// where our postcard+COBS frame from [`protocol`](crate::protocol)
+    /// actually lives.
+    #[task(
+        binds = UART4,
+        priority = 3,
+        local = [
+            lora_rx,
+            line_buf: Vec<u8, MAX_FRAME> = Vec::new(),
+            overflowed: bool = false,
+        ],
+    )]
     fn uart4_handler(cx: uart4_handler::Context) {
-        let mut uart_res = cx.shared.lora_uart;
-        let mut buffer_res = cx.shared.rx_buffer;
-        let mut display_res = cx.shared.display;
-
-        uart_res.lock(|uart| {
-            if let Ok(byte) = uart.read() {
-                buffer_res.lock(|buffer| {
-                    if byte == b'\n' {
-                        // Sentence complete!
-                        if let Ok(s) = core::str::from_utf8(buffer.as_slice()) {
-                            let clean_str = s.trim();
-                            info!("Sentence: {}", clean_str);
-                            
-                            display_res.lock(|display| {
-                                display.clear(BinaryColor::Off).unwrap();
-                                let text_style = MonoTextStyleBuilder::new()
-                                    .font(&FONT_6X10)
-                                    .text_color(BinaryColor::On)
-                                    .build();
-                                
-                                Text::new(clean_str, Point::new(0, 20), text_style)
-                                    .draw(display)
-                                    .unwrap();
-                                
-                                display.flush().unwrap();
-                            });
-                        }
-                        buffer.clear();
-                    } else if byte != b'\r' && !buffer.is_full() {
-                        let _ = buffer.push(byte);
-                    }
-                });
+        let Ok(byte) = cx.local.lora_rx.read() else {
+            return;
+        };
+
+        if byte == b'\r' {
+            return;
+        }
+
+        if byte == b'\n' {
+            if !*cx.local.overflowed {
+                let _ = process_frame::spawn(cx.local.line_buf.clone());
+            }
+            cx.local.line_buf.clear();
+            *cx.local.overflowed = false;
+            return;
+        }
+
+        if cx.local.line_buf.push(byte).is_err() {
+            // Line too long for MAX_FRAME: mark it for whole-line
+            // discard instead of letting a partial line desync
+            // anything downstream.
+            *cx.local.overflowed = true;
+        }
+    }
+
+    /// Owns `display` and does the slow work: parse the line handed to
+    /// it by `uart4_handler` and render. Runs at priority 2, below the
+    /// priority-3 hardware RX ISRs (`uart4_handler`, `usart2_handler`).
+    /// Its blocking AT-command writes (via `handle_command`/
+    /// `apply_config_at`) lock `lora_tx`, not `lora_rx` — `uart4_handler`
+    /// only ever touches the latter as a `local` resource, so those
+    /// writes can't mask it regardless of priority.
+    #[task(
+        priority = 2,
+        shared = [display, lora_tx, config],
+        local = [
+            rssi_filter: RssiFilter = RssiFilter::new(),
+            log_tx,
+            log_seq,
+        ],
+    )]
+    fn process_frame(mut cx: process_frame::Context, line: Vec<u8, MAX_FRAME>) {
+        // `line` is the raw `+RCV=...` line, including the binary
+        // postcard+COBS `data` field — it's essentially never valid
+        // UTF-8 as a whole, so `parse_rcv` works on bytes directly and
+        // only the genuinely-ASCII `addr`/`len`/`rssi`/`snr` fields are
+        // ever decoded as text.
+        let bytes = line.as_slice();
+
+        let Some(rcv) = rylr::parse_rcv(bytes) else {
+            info!("Unparsed line ({} bytes)", bytes.len());
+            return;
+        };
+
+        let avg_rssi = cx.local.rssi_filter.push(rcv.rssi);
+
+        *cx.local.log_seq += 1;
+        let mut log_payload: Vec<u8, MAX_PAYLOAD> = Vec::new();
+        let take = rcv.data.len().min(MAX_PAYLOAD);
+        let _ = log_payload.extend_from_slice(&rcv.data[..take]);
+        let record = LogRecord {
+            seq: *cx.local.log_seq,
+            rssi: rcv.rssi,
+            snr: rcv.snr,
+            payload: log_payload,
+        };
+        if cx.local.log_tx.enqueue(record).is_ok() {
+            let _ = sd_log::spawn();
+        }
+
+        let mut payload: Vec<u8, MAX_FRAME> = Vec::new();
+        let _ = payload.extend_from_slice(rcv.data);
+        let text: heapless::String<MAX_FRAME> = match protocol::decode_frame(payload.as_mut_slice()) {
+            Ok(Message::Command(cmd)) => {
+                handle_command(&mut cx, &cmd);
+                message_text(&Message::Command(cmd))
+            }
+            Ok(msg) => {
+                info!("Frame: {}", defmt::Debug2Format(&msg));
+                message_text(&msg)
+            }
+            Err(_) => match core::str::from_utf8(rcv.data) {
+                Ok(text) => heapless::String::try_from(text).unwrap_or_default(),
+                Err(_) => heapless::String::try_from("<binary>").unwrap_or_default(),
+            },
+        };
+
+        let _ = cw_play::spawn(text.clone());
+
+        cx.shared
+            .display
+            .lock(|display| render_signal(display, text.as_str(), avg_rssi));
+    }
+
+    /// Applies a received [`CommandKind`] to the shared config, persists
+    /// it to flash if it actually changed anything, and re-programs the
+    /// module over AT commands.
+    fn handle_command(cx: &mut process_frame::Context, cmd: &CommandKind) {
+        info!("Command: {}", defmt::Debug2Format(cmd));
+
+        let Some(config) = cx.shared.config.lock(|config| config.apply(cmd).then_some(*config)) else {
+            return;
+        };
+
+        let _ = persist_config::spawn(config);
+        cx.shared.lora_tx.lock(|tx| apply_config_at(tx, &config));
+    }
+
+    /// Formats a decoded [`Message`] as a single display line.
+    fn message_text(msg: &Message) -> heapless::String<MAX_FRAME> {
+        let mut line = heapless::String::new();
+        let _ = match msg {
+            Message::Telemetry { seq, rssi, payload } => {
+                let text = core::str::from_utf8(payload.as_slice()).unwrap_or("<binary>");
+                core::fmt::write(&mut line, format_args!("#{seq} {rssi}dBm {text}"))
+            }
+            Message::Ack { seq } => core::fmt::write(&mut line, format_args!("ACK #{seq}")),
+            Message::Command(cmd) => core::fmt::write(&mut line, format_args!("CMD {cmd:?}")),
+        };
+        line
+    }
+
+    /// Renders `text` alongside a signal-strength bar scaled from
+    /// `avg_rssi` (dBm).
+    fn render_signal(display: &mut LoraDisplay, text: &str, avg_rssi: i16) {
+        display.clear(BinaryColor::Off).unwrap();
+        let text_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X10)
+            .text_color(BinaryColor::On)
+            .build();
+
+        Text::new(text, Point::new(0, 20), text_style)
+            .draw(display)
+            .unwrap();
+
+        let width = signal::bar_width_px(avg_rssi);
+        Rectangle::new(Point::new(0, 28), Size::new(width as u32, 6))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(display)
+            .unwrap();
+
+        display.flush().unwrap();
+    }
+
+    /// Accumulates GPS UART bytes into a line and hands complete NMEA
+    /// sentences to the parser. Parsing a single sentence is cheap
+    /// enough to do inline here; only the display redraw is spawned out.
+    ///
+    /// Runs at priority 3, the same tier as `uart4_handler` and for the
+    /// same reason: it's a hardware RX ISR and must not be maskable by
+    /// `process_frame`'s slower, occasionally-blocking work.
+    #[task(binds = USART2, priority = 3, shared = [gps_fix], local = [gps_uart, gps_line])]
+    fn usart2_handler(mut cx: usart2_handler::Context) {
+        let Ok(byte) = cx.local.gps_uart.read() else {
+            return;
+        };
+
+        if byte == b'\r' {
+            return;
+        }
+        if byte != b'\n' {
+            if cx.local.gps_line.is_full() {
+                cx.local.gps_line.clear();
+            }
+            let _ = cx.local.gps_line.push(byte);
+            return;
+        }
+
+        if let Ok(line) = core::str::from_utf8(cx.local.gps_line.as_slice()) {
+            if let Some(fix) = nmea::parse_sentence(line) {
+                cx.shared.gps_fix.lock(|shared_fix| *shared_fix = Some(fix));
+                let _ = gps_status::spawn();
             }
+        }
+        cx.local.gps_line.clear();
+    }
+
+    /// Owns `display` to redraw the current fix and Maidenhead locator
+    /// whenever a new GPS sentence updates `gps_fix`.
+    #[task(shared = [display, gps_fix])]
+    fn gps_status(mut cx: gps_status::Context) {
+        let Some(fix) = cx.shared.gps_fix.lock(|fix| *fix) else {
+            return;
+        };
+        let locator = locator::maidenhead(fix.lat, fix.lon);
+
+        let mut line: heapless::String<32> = heapless::String::new();
+        let _ = core::fmt::write(
+            &mut line,
+            format_args!("{:.3},{:.3} {}", fix.lat, fix.lon, locator.as_str()),
+        );
+
+        cx.shared.display.lock(|display| {
+            display.clear(BinaryColor::Off).unwrap();
+            let text_style = MonoTextStyleBuilder::new()
+                .font(&FONT_6X10)
+                .text_color(BinaryColor::On)
+                .build();
+            Text::new(line.as_str(), Point::new(0, 10), text_style)
+                .draw(display)
+                .unwrap();
+            display.flush().unwrap();
+        });
+    }
+
+    /// Drains whatever frames piled up in the log queue and appends
+    /// them to the SD card. Runs at the default (lowest) priority and
+    /// never blocks the UART or display paths — if the card is absent
+    /// or a write fails, `SdLogger` just drops back to display-only
+    /// operation; `log()` stays a no-op until `mount()` succeeds again,
+    /// and only `init` calls `mount()`.
+    #[task(local = [log_rx, sd_logger])]
+    fn sd_log(cx: sd_log::Context) {
+        while let Some(record) = cx.local.log_rx.dequeue() {
+            let _ = cx.local.sd_logger.log(&record);
+        }
+    }
+
+    /// Erases and reprograms the config flash sector. Owns `flash`
+    /// exclusively so callers (the command handler, the menu button)
+    /// just hand it a [`Config`] snapshot to write.
+    #[task(local = [flash])]
+    fn persist_config(cx: persist_config::Context, new_config: Config) {
+        config::persist(cx.local.flash, &new_config);
+    }
+
+    /// Samples the QEI counter at a fixed 100Hz rate and converts
+    /// accumulated quadrature counts into debounced encoder detents: a
+    /// rotation only registers once it has moved a full
+    /// `COUNTS_PER_DETENT`, so electrical noise within a detent is
+    /// absorbed rather than jittering the menu back and forth.
+    #[task(binds = TIM4, shared = [menu, config], local = [encoder, encoder_count, menu_timer])]
+    fn encoder_poll(mut cx: encoder_poll::Context) {
+        cx.local.menu_timer.clear_flags(Flag::Update);
+
+        let count = cx.local.encoder.count();
+        let delta_counts = count.wrapping_sub(*cx.local.encoder_count) as i16;
+        let detents = delta_counts / COUNTS_PER_DETENT;
+        if detents == 0 {
+            return;
+        }
+        // Only consume the counts that made up a whole detent; leftover
+        // sub-detent counts stay pending for the next sample.
+        *cx.local.encoder_count = cx.local.encoder_count.wrapping_add((detents * COUNTS_PER_DETENT) as u16);
+
+        cx.shared.menu.lock(|menu| {
+            cx.shared.config.lock(|config| menu.rotate(detents as i32, config));
         });
+
+        let _ = menu_redraw::spawn();
+    }
+
+    /// Debounced-by-hardware-pull-up button edge: advances the menu
+    /// state machine, and on confirming an edit, persists the new
+    /// config to flash and re-programs the module over AT.
+    #[task(binds = EXTI15_10, shared = [menu, config, lora_tx], local = [button])]
+    fn button_handler(mut cx: button_handler::Context) {
+        cx.local.button.clear_interrupt_pending_bit();
+
+        let confirmed = cx.shared.menu.lock(|menu| menu.press());
+        if confirmed {
+            let config = cx.shared.config.lock(|config| *config);
+            let _ = persist_config::spawn(config);
+            cx.shared.lora_tx.lock(|tx| apply_config_at(tx, &config));
+        }
+
+        let _ = menu_redraw::spawn();
     }
 
-    #[task(binds = TIM2, shared = [lora_uart], local = [led, timer])]
+    /// Owns `display` to redraw the current menu screen. Only spawned
+    /// when the encoder or button actually changed `menu`/`config`, so
+    /// this never competes with frame/GPS rendering for no reason.
+    #[task(shared = [display, menu, config])]
+    fn menu_redraw(mut cx: menu_redraw::Context) {
+        let (menu, config) = (
+            cx.shared.menu.lock(|menu| *menu),
+            cx.shared.config.lock(|config| *config),
+        );
+
+        let (field, editing) = match menu {
+            Menu::Browse { field } => (field, false),
+            Menu::Edit { field } => (field, true),
+        };
+
+        let value: u32 = match field {
+            Field::Address => config.address as u32,
+            Field::NetworkId => config.network_id as u32,
+            Field::TxPower => config.tx_power as u32,
+            Field::SpreadingFactor => config.spreading_factor as u32,
+            Field::BeaconEnabled => (config.beacon_interval_s > 0) as u32,
+            Field::CwWpm => config.cw_wpm as u32,
+        };
+
+        let mut line: heapless::String<32> = heapless::String::new();
+        let marker = if editing { '>' } else { ' ' };
+        let _ = core::fmt::write(&mut line, format_args!("{marker}{} {value}", field.label()));
+
+        cx.shared.display.lock(|display| {
+            display.clear(BinaryColor::Off).unwrap();
+            let text_style = MonoTextStyleBuilder::new()
+                .font(&FONT_6X10)
+                .text_color(BinaryColor::On)
+                .build();
+            Text::new(line.as_str(), Point::new(0, 40), text_style)
+                .draw(display)
+                .unwrap();
+            display.flush().unwrap();
+        });
+    }
+
+    /// Toggles the heartbeat LED every second and, every
+    /// `config.beacon_interval_s` ticks (0 = disabled, toggled from the
+    /// encoder menu's `BEACON` field), beacons the station's Maidenhead
+    /// locator over LoRa in place of the old `AT+ADDRESS?` placeholder.
+    #[task(binds = TIM2, shared = [lora_tx, gps_fix, config], local = [led, timer, beacon_seq, beacon_tick])]
     fn tim2_handler(mut cx: tim2_handler::Context) {
         cx.local.timer.clear_flags(Flag::Update);
         cx.local.led.toggle();
 
-        cx.shared.lora_uart.lock(|uart| {
-            // Victorious Query!
-            for b in b"AT+ADDRESS?\r\n" {
-                let _ = nb::block!(uart.write(*b));
+        let interval = cx.shared.config.lock(|config| config.beacon_interval_s);
+        if interval == 0 {
+            return;
+        }
+
+        *cx.local.beacon_tick += 1;
+        if *cx.local.beacon_tick < interval {
+            return;
+        }
+        *cx.local.beacon_tick = 0;
+
+        let Some(fix) = cx.shared.gps_fix.lock(|fix| *fix) else {
+            info!("Beacon skipped: no GPS fix yet");
+            return;
+        };
+
+        let locator = locator::maidenhead(fix.lat, fix.lon);
+        let mut payload: Vec<u8, MAX_PAYLOAD> = Vec::new();
+        let _ = payload.extend_from_slice(locator.as_bytes());
+
+        *cx.local.beacon_seq = cx.local.beacon_seq.wrapping_add(1);
+        let msg = Message::Telemetry { seq: *cx.local.beacon_seq, rssi: 0, payload };
+
+        let Ok(frame) = protocol::encode_frame(&msg) else {
+            info!("Beacon encode failed");
+            return;
+        };
+
+        cx.shared.lora_tx.lock(|tx| {
+            let mut cmd: heapless::String<32> = heapless::String::new();
+            let _ = core::fmt::write(
+                &mut cmd,
+                format_args!("AT+SEND={BEACON_ADDRESS},{},", frame.len()),
+            );
+            for b in cmd.as_bytes() {
+                let _ = nb::block!(tx.write(*b));
+            }
+            for b in frame.as_slice() {
+                let _ = nb::block!(tx.write(*b));
+            }
+            for b in b"\r\n" {
+                let _ = nb::block!(tx.write(*b));
             }
         });
     }
+
+    /// Encodes `text` as Morse and enqueues its elements for `cw_tick`
+    /// to play. Runs at default priority so it never competes with
+    /// `process_frame` for the UART/display; if the queue fills (a
+    /// message arrived while a previous one is still playing), the
+    /// remainder is dropped rather than blocking the caller.
+    #[task(local = [cw_tx])]
+    fn cw_play(cx: cw_play::Context, text: heapless::String<MAX_FRAME>) {
+        let mut elements: Vec<Element, MAX_ELEMENTS> = Vec::new();
+        cw::encode(text.as_str(), &mut elements);
+
+        for element in elements {
+            if cx.local.cw_tx.enqueue(element).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Fixed-rate CW keyer tick (see `CW_TICK_HZ`): counts down the
+    /// remaining duration of the current element, and once it elapses,
+    /// pops the next one off the queue and gates the sidetone's PWM
+    /// duty (0% silent, 50% tone) accordingly. Runs continuously, idle
+    /// or not, so a freshly enqueued message starts on the very next
+    /// tick rather than needing a separate start signal from `cw_play`.
+    #[task(binds = TIM5, shared = [config], local = [cw_rx, cw_timer, cw_pwm, cw_remaining_ticks])]
+    fn cw_tick(mut cx: cw_tick::Context) {
+        cx.local.cw_timer.clear_flags(Flag::Update);
+
+        if *cx.local.cw_remaining_ticks > 0 {
+            *cx.local.cw_remaining_ticks -= 1;
+            return;
+        }
+
+        let Some(element) = cx.local.cw_rx.dequeue() else {
+            cx.local.cw_pwm.set_duty(0);
+            return;
+        };
+
+        let (units, tone_on) = match element {
+            Element::Mark(units) => (units, true),
+            Element::Space(units) => (units, false),
+        };
+
+        let max_duty = cx.local.cw_pwm.get_max_duty();
+        cx.local.cw_pwm.set_duty(if tone_on { max_duty / 2 } else { 0 });
+
+        let wpm = cx.shared.config.lock(|config| config.cw_wpm);
+        let dot_ticks = (cw::dot_millis(wpm) / CW_TICK_MS).max(1);
+        *cx.local.cw_remaining_ticks = dot_ticks * units as u32 - 1;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+//! On-device menu state machine for live LoRa parameter tuning via a
+//! rotary encoder and push button.
+
+use crate::config::Config;
+
+/// A field the menu can browse to and edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Address,
+    NetworkId,
+    TxPower,
+    SpreadingFactor,
+    BeaconEnabled,
+    CwWpm,
+}
+
+impl Field {
+    const ALL: [Field; 6] = [
+        Field::Address,
+        Field::NetworkId,
+        Field::TxPower,
+        Field::SpreadingFactor,
+        Field::BeaconEnabled,
+        Field::CwWpm,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|f| *f == self).unwrap()
+    }
+
+    /// Steps `steps` positions forward (negative = backward) through
+    /// the field list, wrapping around.
+    fn step(self, steps: i32) -> Self {
+        let len = Self::ALL.len() as i32;
+        let idx = self.index() as i32;
+        let next = (idx + steps).rem_euclid(len);
+        Self::ALL[next as usize]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Address => "ADDR",
+            Field::NetworkId => "NETID",
+            Field::TxPower => "TXPWR",
+            Field::SpreadingFactor => "SF",
+            Field::BeaconEnabled => "BEACON",
+            Field::CwWpm => "CWWPM",
+        }
+    }
+}
+
+/// Current menu screen: scrolling through fields, or editing one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Menu {
+    Browse { field: Field },
+    Edit { field: Field },
+}
+
+impl Menu {
+    pub const fn new() -> Self {
+        Menu::Browse { field: Field::Address }
+    }
+
+    /// Applies `delta` encoder detents: scrolls the selected field in
+    /// `Browse`, or adjusts its value in `Edit`.
+    pub fn rotate(&mut self, delta: i32, config: &mut Config) {
+        match *self {
+            Menu::Browse { field } => *self = Menu::Browse { field: field.step(delta) },
+            Menu::Edit { field } => match field {
+                Field::Address => {
+                    config.address = (config.address as i32 + delta).clamp(0, u16::MAX as i32) as u16
+                }
+                Field::NetworkId => {
+                    config.network_id = (config.network_id as i32 + delta).clamp(0, u8::MAX as i32) as u8
+                }
+                Field::TxPower => {
+                    config.tx_power = (config.tx_power as i32 + delta).clamp(0, 22) as u8
+                }
+                Field::SpreadingFactor => {
+                    config.spreading_factor = (config.spreading_factor as i32 + delta).clamp(6, 12) as u8
+                }
+                Field::BeaconEnabled => {
+                    if delta != 0 {
+                        config.beacon_interval_s = if config.beacon_interval_s == 0 { 30 } else { 0 };
+                    }
+                }
+                Field::CwWpm => {
+                    config.cw_wpm = (config.cw_wpm as i32 + delta).clamp(5, 40) as u8
+                }
+            },
+        }
+    }
+
+    /// Button press: enter edit mode for the selected field, or confirm
+    /// the edit and return to browsing. Returns `true` if this press
+    /// just confirmed an edit (i.e. the caller should commit `config`).
+    pub fn press(&mut self) -> bool {
+        match *self {
+            Menu::Browse { field } => {
+                *self = Menu::Edit { field };
+                false
+            }
+            Menu::Edit { field } => {
+                *self = Menu::Browse { field };
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Minimal NMEA-0183 parsing for `$GPRMC` / `$GPGGA` sentences — just
+//! enough to recover a lat/lon fix for the locator beacon.
+
+/// A decoded GPS fix (decimal degrees, +N/+E).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpsFix {
+    pub lat: f32,
+    pub lon: f32,
+}
+
+/// Parses a single NMEA sentence, returning a fix if it's a `$GPRMC` or
+/// `$GPGGA` sentence reporting a valid position. Anything else (other
+/// sentence types, checksums stripped or not, void fixes) yields `None`.
+pub fn parse_sentence(line: &str) -> Option<GpsFix> {
+    let mut fields = line.trim().split(',');
+    match fields.next()? {
+        "$GPRMC" => parse_rmc(fields),
+        "$GPGGA" => parse_gga(fields),
+        _ => None,
+    }
+}
+
+fn parse_rmc<'a>(mut fields: impl Iterator<Item = &'a str>) -> Option<GpsFix> {
+    let _time = fields.next()?;
+    if fields.next()? != "A" {
+        return None; // void fix
+    }
+    let lat = dm_to_decimal(fields.next()?)?;
+    let lat = apply_hemisphere(lat, fields.next()?, 'S');
+    let lon = dm_to_decimal(fields.next()?)?;
+    let lon = apply_hemisphere(lon, fields.next()?, 'W');
+    Some(GpsFix { lat, lon })
+}
+
+fn parse_gga<'a>(mut fields: impl Iterator<Item = &'a str>) -> Option<GpsFix> {
+    let _time = fields.next()?;
+    let lat = dm_to_decimal(fields.next()?)?;
+    let lat = apply_hemisphere(lat, fields.next()?, 'S');
+    let lon = dm_to_decimal(fields.next()?)?;
+    let lon = apply_hemisphere(lon, fields.next()?, 'W');
+    if fields.next()? == "0" {
+        return None; // fix quality 0 = invalid
+    }
+    Some(GpsFix { lat, lon })
+}
+
+/// Converts an NMEA `ddmm.mmmm` / `dddmm.mmmm` degrees-minutes field into
+/// decimal degrees. `field` is always non-negative; hemisphere is applied
+/// separately by the caller.
+fn dm_to_decimal(field: &str) -> Option<f32> {
+    if field.is_empty() {
+        return None;
+    }
+    let value: f32 = field.parse().ok()?;
+    let degrees = (value / 100.0) as i32 as f32;
+    let minutes = value - degrees * 100.0;
+    Some(degrees + minutes / 60.0)
+}
+
+fn apply_hemisphere(value: f32, hemisphere: &str, negative: char) -> f32 {
+    if hemisphere.starts_with(negative) {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_gprmc_fix() {
+        let line = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+        let fix = parse_sentence(line).expect("should parse a valid fix");
+        assert!((fix.lat - 48.1173).abs() < 0.001, "lat = {}", fix.lat);
+        assert!((fix.lon - 11.5167).abs() < 0.001, "lon = {}", fix.lon);
+    }
+
+    #[test]
+    fn rejects_void_gprmc_fix() {
+        let line = "$GPRMC,123519,V,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+        assert!(parse_sentence(line).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_sentence() {
+        assert!(parse_sentence("$GPGSA,A,3,,,,,,,,,,,,*3E").is_none());
+    }
+
+    #[test]
+    fn applies_southern_and_western_hemispheres() {
+        let line = "$GPRMC,123519,A,4807.038,S,01131.000,W,022.4,084.4,230394,003.1,W*6A";
+        let fix = parse_sentence(line).expect("should parse");
+        assert!(fix.lat < 0.0);
+        assert!(fix.lon < 0.0);
+    }
+}
@@ -0,0 +1,92 @@
+//! On-air message framing for the LoRa link.
+//!
+//! Frames are `postcard` encoded and COBS stuffed (`to_vec_cobs` /
+//! `from_bytes_cobs`), so the wire format is guaranteed free of `0x00`
+//! bytes with a single `0x00` marking the end of a frame. Frames travel
+//! as the `<data>` field of the radio module's `\n`-terminated `+RCV`
+//! lines (see [`crate::rylr`]), and everything above the parser works
+//! with typed messages instead of raw text.
+
+use heapless::Vec;
+use postcard::{from_bytes_cobs, to_vec_cobs};
+use serde::{Deserialize, Serialize};
+
+/// Largest payload we'll carry inside a `Telemetry` message.
+pub const MAX_PAYLOAD: usize = 32;
+
+/// Largest encoded (COBS stuffed) frame we expect on the wire, including
+/// the trailing `0x00` delimiter.
+pub const MAX_FRAME: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+    /// A received (or to-be-sent) application payload.
+    Telemetry {
+        seq: u16,
+        rssi: i16,
+        payload: Vec<u8, MAX_PAYLOAD>,
+    },
+    /// Acknowledges delivery of `seq`.
+    Ack { seq: u16 },
+    /// A configuration/control command, see [`CommandKind`].
+    Command(CommandKind),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CommandKind {
+    SetAddress(u16),
+    SetNetworkId(u8),
+    SetBeaconInterval(u16),
+}
+
+/// Encodes `msg` into a COBS-stuffed, `0x00`-delimited frame ready to
+/// push onto the UART TX path.
+pub fn encode_frame(msg: &Message) -> Result<Vec<u8, MAX_FRAME>, postcard::Error> {
+    to_vec_cobs(msg)
+}
+
+/// Decodes a single COBS-stuffed frame (with or without the trailing
+/// `0x00`) back into a [`Message`]. `frame` is mutated in place, as
+/// required by `from_bytes_cobs`.
+pub fn decode_frame(frame: &mut [u8]) -> Result<Message, postcard::Error> {
+    from_bytes_cobs(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_telemetry() {
+        let mut payload: Vec<u8, MAX_PAYLOAD> = Vec::new();
+        payload.extend_from_slice(b"hello").unwrap();
+        let msg = Message::Telemetry { seq: 42, rssi: -75, payload };
+
+        let mut frame = encode_frame(&msg).expect("encode");
+        match decode_frame(&mut frame).expect("decode") {
+            Message::Telemetry { seq, rssi, payload } => {
+                assert_eq!(seq, 42);
+                assert_eq!(rssi, -75);
+                assert_eq!(payload.as_slice(), b"hello");
+            }
+            other => panic!("expected Telemetry, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn round_trips_command() {
+        let msg = Message::Command(CommandKind::SetNetworkId(7));
+
+        let mut frame = encode_frame(&msg).expect("encode");
+        match decode_frame(&mut frame).expect("decode") {
+            Message::Command(CommandKind::SetNetworkId(id)) => assert_eq!(id, 7),
+            other => panic!("expected Command(SetNetworkId), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decode_rejects_empty_frame() {
+        let mut empty: [u8; 0] = [];
+        assert!(decode_frame(&mut empty).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+//! Parsing for RYLR-style AT unsolicited notifications, e.g.
+//! `+RCV=<addr>,<len>,<data>,<rssi>,<snr>`.
+//!
+//! Only `<addr>`, `<len>`, `<rssi>`, `<snr>` are genuinely ASCII text —
+//! `<data>` is the postcard+COBS frame from [`crate::protocol`], which
+//! is arbitrary binary (postcard varints routinely set the high bit),
+//! so the whole notification is parsed as bytes rather than `str` and
+//! `data` is handed back as an opaque byte slice, never validated as
+//! UTF-8.
+
+/// A decoded `+RCV` notification. Borrows `data` from the line it was
+/// parsed from.
+#[derive(Debug, Clone, Copy)]
+pub struct RcvFrame<'a> {
+    pub addr: u16,
+    pub len: usize,
+    pub data: &'a [u8],
+    pub rssi: i16,
+    pub snr: i16,
+}
+
+const PREFIX: &[u8] = b"+RCV=";
+
+/// Splits a `+RCV=...` line into its fields. Returns `None` if `line`
+/// isn't a `+RCV` notification or is malformed.
+///
+/// `data` is a COBS frame, so `0x00` appears in it only as the trailing
+/// delimiter — it may legally contain `,` bytes or non-UTF-8 bytes. So unlike the other (ASCII) fields,
+/// `data` is never decoded as text or split on `,`; it's sliced as raw
+/// bytes to exactly the length given by the already-parsed `<len>`
+/// field.
+pub fn parse_rcv(line: &[u8]) -> Option<RcvFrame<'_>> {
+    let rest = line.strip_prefix(PREFIX)?;
+
+    let (addr_str, rest) = split_once(rest, b',')?;
+    let addr = ascii(addr_str)?.trim().parse().ok()?;
+
+    let (len_str, rest) = split_once(rest, b',')?;
+    let len: usize = ascii(len_str)?.trim().parse().ok()?;
+
+    let data = rest.get(..len)?;
+    let (comma, rest) = rest.get(len..)?.split_first()?;
+    if *comma != b',' {
+        return None;
+    }
+
+    let (rssi_str, snr_str) = split_once(rest, b',')?;
+    let rssi = ascii(rssi_str)?.trim().parse().ok()?;
+    let snr = ascii(snr_str)?.trim().parse().ok()?;
+
+    Some(RcvFrame { addr, len, data, rssi, snr })
+}
+
+/// Splits `bytes` on the first occurrence of `delim`, as `&str`'s
+/// `split_once` does for `char`.
+fn split_once(bytes: &[u8], delim: u8) -> Option<(&[u8], &[u8])> {
+    let pos = bytes.iter().position(|&b| b == delim)?;
+    Some((&bytes[..pos], &bytes[pos + 1..]))
+}
+
+fn ascii(bytes: &[u8]) -> Option<&str> {
+    core::str::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_data_containing_commas() {
+        let line = b"+RCV=10,5,ab,cd,-80,9";
+        let rcv = parse_rcv(line).expect("should parse");
+        assert_eq!(rcv.addr, 10);
+        assert_eq!(rcv.len, 5);
+        assert_eq!(rcv.data, b"ab,cd");
+        assert_eq!(rcv.rssi, -80);
+        assert_eq!(rcv.snr, 9);
+    }
+
+    #[test]
+    fn parses_non_utf8_data() {
+        let line = b"+RCV=10,3,\xFF\x01\xFE,-80,9";
+        let rcv = parse_rcv(line).expect("should parse");
+        assert_eq!(rcv.data, [0xFF, 0x01, 0xFE]);
+    }
+
+    #[test]
+    fn rejects_non_rcv_line() {
+        assert!(parse_rcv(b"+OK").is_none());
+    }
+}
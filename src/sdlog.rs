@@ -0,0 +1,103 @@
+//! SD-card logging of decoded LoRa frames, over SPI via
+//! `embedded-sdmmc`. Card I/O is slow and fallible, so callers must only
+//! drive this from a dedicated low-priority task, never from an ISR —
+//! see `sd_log` in `main.rs`.
+
+use crate::protocol::MAX_PAYLOAD;
+use embedded_sdmmc::{Mode, SdCard, TimeSource, Timestamp, VolumeIdx, VolumeManager};
+use heapless::{String, Vec};
+
+/// Log file name (8.3 format). Every record is appended to this one
+/// file; it is never rotated.
+pub const LOG_FILE: &str = "LORA.LOG";
+
+/// One decoded frame, ready to be formatted as a log line.
+#[derive(Clone)]
+pub struct LogRecord {
+    pub seq: u32,
+    pub rssi: i16,
+    pub snr: i16,
+    pub payload: Vec<u8, MAX_PAYLOAD>,
+}
+
+/// Formats a record as one CSV line: `seq,rssi,snr,payload`.
+pub fn format_line(record: &LogRecord) -> String<128> {
+    let text = core::str::from_utf8(&record.payload).unwrap_or("<binary>");
+    let mut line = String::new();
+    let _ = core::fmt::write(
+        &mut line,
+        format_args!("{},{},{},{}\n", record.seq, record.rssi, record.snr, text),
+    );
+    line
+}
+
+/// This board has no RTC, so every record is stamped with a fixed epoch
+/// rather than a real timestamp.
+pub struct NoRtc;
+
+impl TimeSource for NoRtc {
+    fn get_timestamp(&self) -> Timestamp {
+        Timestamp {
+            year_since_1970: 54, // 2024
+            zero_indexed_month: 0,
+            zero_indexed_day: 0,
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+        }
+    }
+}
+
+/// Appends decoded frames to [`LOG_FILE`] on a FAT-formatted SD card.
+/// Degrades to a no-op if the card is absent or any operation fails —
+/// logging is best-effort, it must never take display/LoRa reception
+/// down with it.
+pub struct SdLogger<SPI, CS, DELAY> {
+    volume_mgr: VolumeManager<SdCard<SPI, CS, DELAY>, NoRtc>,
+    present: bool,
+}
+
+impl<SPI, CS, DELAY> SdLogger<SPI, CS, DELAY>
+where
+    SdCard<SPI, CS, DELAY>: embedded_sdmmc::BlockDevice,
+{
+    pub fn new(card: SdCard<SPI, CS, DELAY>) -> Self {
+        Self {
+            volume_mgr: VolumeManager::new(card, NoRtc),
+            present: false,
+        }
+    }
+
+    /// Probes for a card. Must be called once before `log`; safe to
+    /// call again later to retry if the card was inserted afterwards.
+    pub fn mount(&mut self) -> bool {
+        self.present = self.volume_mgr.open_volume(VolumeIdx(0)).is_ok();
+        self.present
+    }
+
+    /// Appends `record` to the log file. Returns `false` (and leaves
+    /// the card un-retried until the next `mount()`) on any failure.
+    pub fn log(&mut self, record: &LogRecord) -> bool {
+        if !self.present {
+            return false;
+        }
+
+        let ok = (|| -> Result<(), embedded_sdmmc::Error<embedded_sdmmc::SdCardError>> {
+            let volume = self.volume_mgr.open_volume(VolumeIdx(0))?;
+            let root = self.volume_mgr.open_root_dir(volume)?;
+            let file = self
+                .volume_mgr
+                .open_file_in_dir(root, LOG_FILE, Mode::ReadWriteCreateOrAppend)?;
+            let line = format_line(record);
+            self.volume_mgr.write(file, line.as_bytes())?;
+            self.volume_mgr.close_file(file)?;
+            self.volume_mgr.close_dir(root)?;
+            Ok(())
+        })();
+
+        if ok.is_err() {
+            self.present = false;
+        }
+        ok.is_ok()
+    }
+}
@@ -0,0 +1,51 @@
+//! RSSI smoothing and signal-bar geometry for the OLED link-quality
+//! indicator.
+
+use heapless::Vec;
+
+/// Number of RSSI samples averaged together.
+pub const WINDOW: usize = 8;
+
+/// Fixed-window moving average over the last [`WINDOW`] RSSI samples.
+pub struct RssiFilter {
+    samples: Vec<i16, WINDOW>,
+    sum: i32,
+}
+
+impl RssiFilter {
+    pub const fn new() -> Self {
+        Self { samples: Vec::new(), sum: 0 }
+    }
+
+    /// Feeds in a new RSSI sample (dBm) and returns the current average.
+    pub fn push(&mut self, rssi: i16) -> i16 {
+        if self.samples.is_full() {
+            self.sum -= self.samples.remove(0) as i32;
+        }
+        let _ = self.samples.push(rssi);
+        self.sum += rssi as i32;
+        (self.sum / self.samples.len() as i32) as i16
+    }
+}
+
+impl Default for RssiFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RSSI range the signal bar is scaled over.
+pub const BAR_MIN_DBM: i16 = -120;
+pub const BAR_MAX_DBM: i16 = -40;
+
+/// Bar width in pixels at `BAR_MAX_DBM` (full scale).
+pub const BAR_WIDTH_PX: i32 = 128;
+
+/// Maps an averaged RSSI (dBm) onto a `0..=BAR_WIDTH_PX` bar width,
+/// clamping to the `BAR_MIN_DBM..=BAR_MAX_DBM` range.
+pub fn bar_width_px(rssi: i16) -> i32 {
+    let clamped = rssi.clamp(BAR_MIN_DBM, BAR_MAX_DBM);
+    let span = (BAR_MAX_DBM - BAR_MIN_DBM) as i32;
+    let offset = (clamped - BAR_MIN_DBM) as i32;
+    (offset * BAR_WIDTH_PX) / span
+}